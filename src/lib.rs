@@ -72,12 +72,73 @@
 //! 1. "FM demodulation using a digital radio and digital signal processing", J.M. Shima,
 //! 1995.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
 extern crate num;
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+extern crate libm;
+
+#[cfg(feature = "std")]
 use std::f32::consts::PI;
+#[cfg(not(feature = "std"))]
+use core::f32::consts::PI;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 use num::complex::Complex32;
 
+/// Compute the complex argument, routing through `libm` on `no_std` targets.
+#[cfg(feature = "std")]
+fn arg(c: Complex32) -> f32 {
+    c.arg()
+}
+
+/// Compute the complex argument, routing through `libm` on `no_std` targets.
+#[cfg(not(feature = "std"))]
+fn arg(c: Complex32) -> f32 {
+    libm::atan2f(c.im, c.re)
+}
+
+/// Compute the cosine, routing through `libm` on `no_std` targets.
+#[cfg(feature = "std")]
+fn cos(x: f32) -> f32 {
+    x.cos()
+}
+
+/// Compute the cosine, routing through `libm` on `no_std` targets.
+#[cfg(not(feature = "std"))]
+fn cos(x: f32) -> f32 {
+    libm::cosf(x)
+}
+
+/// Compute the sine, routing through `libm` on `no_std` targets.
+#[cfg(feature = "std")]
+fn sin(x: f32) -> f32 {
+    x.sin()
+}
+
+/// Compute the sine, routing through `libm` on `no_std` targets.
+#[cfg(not(feature = "std"))]
+fn sin(x: f32) -> f32 {
+    libm::sinf(x)
+}
+
+/// Compute the complex magnitude, routing through `libm` on `no_std` targets.
+#[cfg(feature = "std")]
+fn norm(c: Complex32) -> f32 {
+    c.norm()
+}
+
+/// Compute the complex magnitude, routing through `libm` on `no_std` targets.
+#[cfg(not(feature = "std"))]
+fn norm(c: Complex32) -> f32 {
+    libm::hypotf(c.re, c.im)
+}
+
 /// Demodulates an FM signal using a phase difference approximation.
 pub struct FmDemod {
     /// Reciprocol of angular frequency deviation, ω<sub>∆</sub><sup>-1</sup>
@@ -100,20 +161,263 @@ impl FmDemod {
         }
     }
 
+    /// Override the output gain, replacing the ω<sub>∆</sub><sup>-1</sup> factor
+    /// computed in `new` with the given value. Useful for callers who want raw
+    /// frequency-in-relation-to-sample-rate output or a custom deemphasis scaling.
+    pub fn with_gain(mut self, gain: f32) -> FmDemod {
+        self.gain = gain;
+        self
+    }
+
     /// Feed in an FM sample, producing the next sample in the demodulated signal.
     pub fn feed(&mut self, sample: Complex32) -> f32 {
         // Compute x[t].
-        let next = (sample * self.prev.conj()).arg() * self.gain;
+        let next = arg(sample * self.prev.conj()) * self.gain;
         self.prev = sample;
 
         next
     }
+
+    /// Feed in a whole block of FM samples, writing the demodulated signal into
+    /// `output`. The running `prev` sample is carried across calls, so buffer
+    /// boundaries are seamless.
+    ///
+    /// `input` and `output` must be the same length.
+    pub fn feed_slice(&mut self, input: &[Complex32], output: &mut [f32]) {
+        assert_eq!(input.len(), output.len());
+
+        for (&sample, out) in input.iter().zip(output.iter_mut()) {
+            *out = self.feed(sample);
+        }
+    }
+}
+
+/// Modulates a baseband signal into a complex FM signal, the inverse of [`FmDemod`].
+pub struct FmMod {
+    /// Angular frequency deviation, ω<sub>∆</sub>.
+    angular_dev: f32,
+    /// Angular carrier offset, ω<sub>c</sub>, added to the phase each sample.
+    angular_offset: f32,
+    /// Running phase accumulator, φ[t].
+    phase: f32,
+}
+
+impl FmMod {
+    /// Create a new `FmMod` with the given frequency deviation f<sub>∆</sub> (Hz) and
+    /// sample rate f<sub>s</sub> (Hz).
+    ///
+    /// The deviation must satisfy the Nyquist limit, f<sub>∆</sub> ≤ f<sub>s</sub> / 2.
+    pub fn new(deviation: u32, sample_rate: u32) -> FmMod {
+        assert!(deviation <= sample_rate / 2);
+
+        FmMod {
+            angular_dev: 2.0 * PI * deviation as f32 / sample_rate as f32,
+            angular_offset: 0.0,
+            phase: 0.0,
+        }
+    }
+
+    /// Set a carrier offset f<sub>c</sub> (Hz) at the given sample rate f<sub>s</sub>
+    /// (Hz), shifting the output signal away from baseband.
+    pub fn with_offset(mut self, offset: i32, sample_rate: u32) -> FmMod {
+        self.angular_offset = 2.0 * PI * offset as f32 / sample_rate as f32;
+        self
+    }
+
+    /// Feed in a baseband sample, producing the next sample of the modulated FM
+    /// signal.
+    pub fn feed(&mut self, x: f32) -> Complex32 {
+        // Compute φ[t], wrapped into (-π, π] to avoid unbounded growth and float
+        // precision loss.
+        self.phase = wrap_phase(self.phase + self.angular_dev * x + self.angular_offset);
+
+        Complex32::new(cos(self.phase), sin(self.phase))
+    }
+}
+
+/// Wrap the given phase (rad) into (-π, π].
+fn wrap_phase(phase: f32) -> f32 {
+    let mut phase = phase;
+
+    while phase > PI {
+        phase -= 2.0 * PI;
+    }
+
+    while phase <= -PI {
+        phase += 2.0 * PI;
+    }
+
+    phase
+}
+
+/// Demodulates a non-coherent M-FSK signal into hard symbol decisions.
+///
+/// Uses the same phase-difference frequency estimate as [`FmDemod`], averaged over
+/// each symbol period and matched against the nearest nominal tone frequency.
+pub struct FskDemod {
+    /// Nominal angular frequency of each symbol's tone, ω<sub>i</sub>.
+    tones: Vec<f32>,
+    /// Number of samples to average per symbol.
+    samples_per_symbol: u32,
+    /// Previous sample, p[t-1].
+    prev: Option<Complex32>,
+    /// Running sum of the frequency estimate over the current symbol period.
+    accum: f32,
+    /// Number of samples accumulated so far in the current symbol period.
+    count: u32,
+}
+
+impl FskDemod {
+    /// Create a new `FskDemod` with the given sample rate f<sub>s</sub> (Hz), symbol
+    /// period in samples, and the nominal tone frequency (Hz) of each of the M
+    /// symbols.
+    ///
+    /// Each tone frequency must satisfy the Nyquist limit, |f<sub>i</sub>| ≤
+    /// f<sub>s</sub> / 2.
+    pub fn new(sample_rate: u32, samples_per_symbol: u32, tones: &[i32]) -> FskDemod {
+        assert!(!tones.is_empty());
+
+        let tones = tones.iter().map(|&tone| {
+            assert!(tone.unsigned_abs() <= sample_rate / 2);
+            2.0 * PI * tone as f32 / sample_rate as f32
+        }).collect();
+
+        FskDemod {
+            tones,
+            samples_per_symbol,
+            prev: None,
+            accum: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Feed in an FM sample, returning the decoded symbol index once a full symbol
+    /// period has been accumulated, or `None` if the symbol period hasn't elapsed yet
+    /// (including on the very first call, which has no valid previous sample).
+    pub fn feed(&mut self, sample: Complex32) -> Option<u8> {
+        let prev = self.prev.replace(sample)?;
+
+        // Compute x[t] and integrate it over the symbol period.
+        self.accum += arg(sample * prev.conj());
+        self.count += 1;
+
+        if self.count < self.samples_per_symbol {
+            return None;
+        }
+
+        let freq = self.accum / self.count as f32;
+
+        self.accum = 0.0;
+        self.count = 0;
+
+        Some(self.nearest_tone(freq))
+    }
+
+    /// Find the index of the tone whose angular frequency is nearest the given
+    /// angular frequency.
+    fn nearest_tone(&self, freq: f32) -> u8 {
+        self.tones.iter()
+            .enumerate()
+            .min_by(|&(_, a), &(_, b)| {
+                (a - freq).abs().partial_cmp(&(b - freq).abs()).unwrap()
+            })
+            .map(|(i, _)| i as u8)
+            .unwrap()
+    }
+}
+
+/// A single-pole de-emphasis low-pass filter, used to recover the correct spectral
+/// shape of analog FM broadcast audio from [`FmDemod`]'s output.
+pub struct Deemphasis {
+    /// Filter coefficient, α = (1/f<sub>s</sub>) / (τ + 1/f<sub>s</sub>).
+    alpha: f32,
+    /// Previous output sample, y[n-1].
+    prev: f32,
+}
+
+impl Deemphasis {
+    /// Create a new `Deemphasis` filter for the given sample rate f<sub>s</sub> (Hz)
+    /// and RC time constant τ (seconds), e.g. 50e-6 or 75e-6 for analog FM broadcast.
+    pub fn new(sample_rate: u32, tau: f32) -> Deemphasis {
+        let t = 1.0 / sample_rate as f32;
+
+        Deemphasis {
+            alpha: t / (tau + t),
+            prev: 0.0,
+        }
+    }
+
+    /// Feed in a sample, producing the next de-emphasized sample.
+    pub fn feed(&mut self, x: f32) -> f32 {
+        self.prev += self.alpha * (x - self.prev);
+        self.prev
+    }
+}
+
+/// A single-zero pre-emphasis high-pass filter, the inverse of [`Deemphasis`], used to
+/// shape analog FM broadcast audio before modulation with [`FmMod`].
+pub struct Preemphasis {
+    /// Filter coefficient, α = (1/f<sub>s</sub>) / (τ + 1/f<sub>s</sub>).
+    alpha: f32,
+    /// Previous input sample, x[n-1].
+    prev: f32,
+}
+
+impl Preemphasis {
+    /// Create a new `Preemphasis` filter for the given sample rate f<sub>s</sub> (Hz)
+    /// and RC time constant τ (seconds), e.g. 50e-6 or 75e-6 for analog FM broadcast.
+    pub fn new(sample_rate: u32, tau: f32) -> Preemphasis {
+        let t = 1.0 / sample_rate as f32;
+
+        Preemphasis {
+            alpha: t / (tau + t),
+            prev: 0.0,
+        }
+    }
+
+    /// Feed in a sample, producing the next pre-emphasized sample, the inverse of
+    /// [`Deemphasis::feed`].
+    pub fn feed(&mut self, x: f32) -> f32 {
+        let y = (x - (1.0 - self.alpha) * self.prev) / self.alpha;
+        self.prev = x;
+        y
+    }
+}
+
+/// Normalizes the magnitude of each sample to unity, removing AM noise and
+/// stabilizing [`FmDemod`]'s phase-difference estimate under deep fades.
+pub struct Limiter {
+    /// Magnitude below which a sample is passed through unchanged rather than
+    /// normalized, to avoid dividing by near-zero noise.
+    floor: f32,
+}
+
+impl Limiter {
+    /// Create a new `Limiter` with the given magnitude floor.
+    pub fn new(floor: f32) -> Limiter {
+        Limiter { floor }
+    }
+
+    /// Feed in a sample, producing the next magnitude-normalized sample.
+    pub fn feed(&self, sample: Complex32) -> Complex32 {
+        let mag = norm(sample);
+
+        if mag < self.floor {
+            sample
+        } else {
+            sample / mag
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    // `vec!` isn't in the prelude under `no_std`.
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
     #[test]
     fn test_fm_demod() {
         // Tests a binary NRZ payload signal.
@@ -122,22 +426,18 @@ mod test {
         let samprate = 48000;
         // Frequency deviation.
         let dev = 4000;
-        // Angular frequency deviation.
-        let angdev = 2.0 * PI * dev as f32 / samprate as f32;
 
         // Data symbols to encode.
         let data = [-1, 1, 1, -1, 1, -1];
 
-        // Generate "received" I/Q.
-        let mut accum = 0.0f32;
+        // Generate "received" I/Q using the modulator under test.
+        let mut m = FmMod::new(dev, samprate);
         let mut sig = vec![];
 
         for &sym in data.iter() {
             // Use 2 samples per symbol.
             for _ in 0..2 {
-                // Compute Riemann sum integral approximation.
-                accum += angdev * sym as f32;
-                sig.push(Complex32::new(accum.cos(), accum.sin()));
+                sig.push(m.feed(sym as f32));
             }
         }
 
@@ -159,4 +459,102 @@ mod test {
         assert_eq!(d.feed(sig.next().unwrap()), -1.0);
         assert_eq!(d.feed(sig.next().unwrap()), -1.0);
     }
+
+    #[test]
+    fn test_feed_slice() {
+        // feed_slice must match sequential feed calls, including across multiple
+        // feed_slice calls carrying `prev` between them.
+        let samprate = 48000;
+        let dev = 4000;
+
+        let mut m = FmMod::new(dev, samprate);
+        let sig: Vec<Complex32> = (0..20)
+            .map(|i| m.feed(if i % 2 == 0 { 1.0 } else { -1.0 }))
+            .collect();
+
+        let mut expected = FmDemod::new(dev, samprate);
+        let want: Vec<f32> = sig.iter().map(|&s| expected.feed(s)).collect();
+
+        let mut got = vec![0.0; sig.len()];
+        let mut d = FmDemod::new(dev, samprate);
+        d.feed_slice(&sig[..10], &mut got[..10]);
+        d.feed_slice(&sig[10..], &mut got[10..]);
+
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn test_with_gain() {
+        let mut d = FmDemod::new(4000, 48000).with_gain(2.0);
+
+        // Load first sample; feed's output is then the phase difference scaled by
+        // the overridden gain rather than the deviation-derived one.
+        d.feed(Complex32::new(1.0, 0.0));
+
+        let next = Complex32::new(0.0, 1.0);
+        assert_eq!(d.feed(next), arg(next) * 2.0);
+    }
+
+    #[test]
+    fn test_fsk_demod() {
+        // Tests a continuous-phase 2FSK signal.
+
+        let samprate = 48000;
+        let samples_per_symbol = 10;
+        let tones = [-4000, 4000];
+
+        let symbols = [0usize, 1, 1, 0, 1];
+
+        // Generate a continuous-phase 2FSK signal from the symbols.
+        let mut phase = 0.0f32;
+        let mut sig = vec![];
+
+        for &sym in symbols.iter() {
+            let w = 2.0 * PI * tones[sym] as f32 / samprate as f32;
+
+            for _ in 0..samples_per_symbol {
+                phase += w;
+                sig.push(Complex32::new(phase.cos(), phase.sin()));
+            }
+        }
+
+        let mut d = FskDemod::new(samprate, samples_per_symbol, &tones);
+        let decoded: Vec<u8> = sig.into_iter().filter_map(|s| d.feed(s)).collect();
+
+        // The very first sample only seeds `prev`, so the first symbol period is one
+        // sample short and the final symbol never completes.
+        assert_eq!(decoded, vec![0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_limiter() {
+        let l = Limiter::new(0.1);
+
+        // A faded sample above the floor is normalized to unit magnitude.
+        let faded = Complex32::new(0.3, 0.4);
+        let out = l.feed(faded);
+        assert!((norm(out) - 1.0).abs() < 1e-6);
+        assert_eq!(arg(out), arg(faded));
+
+        // A sample below the floor is passed through unchanged.
+        let tiny = Complex32::new(0.01, 0.02);
+        assert_eq!(l.feed(tiny), tiny);
+    }
+
+    #[test]
+    fn test_deemphasis_preemphasis_roundtrip() {
+        let samprate = 48000;
+        let tau = 75e-6;
+
+        let mut pre = Preemphasis::new(samprate, tau);
+        let mut de = Deemphasis::new(samprate, tau);
+
+        // In steady state, de-emphasizing a pre-emphasized constant signal recovers
+        // the original value.
+        let x = 0.5f32;
+
+        for _ in 0..1000 {
+            assert!((de.feed(pre.feed(x)) - x).abs() < 1e-3);
+        }
+    }
 }